@@ -17,6 +17,29 @@
 //! windows-1258 or converting precomposed Vietnamese text into a form that looks
 //! like it was written with the (non-IME) Vietnamese keyboard layout (e.g. for
 //! machine learning training or benchmarking purposes).
+//!
+//! The inverse direction is also provided: an iterator adapter that takes a
+//! sequence of base letters followed by combining Vietnamese tone marks and
+//! recomposes them into precomposed Normalization Form C `char`s, which is
+//! useful for turning decomposed (e.g. keyboard-style) input back into
+//! ordinary display text.
+//!
+//! Additionally, a pair of iterator adapters convert to and from VIQR, the
+//! 7-bit ASCII mnemonic convention for Vietnamese, which is useful for
+//! producing or consuming pure-ASCII Vietnamese text (e.g. as training data).
+//!
+//! An iterator adapter encodes a `char` stream directly into windows-1258
+//! bytes, running the `Windows1258` decomposition internally so callers
+//! don't need to wire up the decomposition and the byte mapping themselves.
+//!
+//! Finally, since `DecomposeVietnamese` assumes NFC input without checking
+//! it, an opt-in `normalize_vietnamese_tones()` adapter composes
+//! base+diacritic+tone sequences and reorders a tone mark that arrives
+//! before a diacritic (circumflex, breve, or horn), so that non-NFC
+//! input (e.g. raw decomposed keystrokes) can be fed into the rest of the
+//! crate without going through a general-purpose normalizer first.
+
+use std::fmt;
 
 #[repr(align(64))] // Align to cache lines
 struct ToneData {
@@ -262,12 +285,85 @@ fn expand(u: u16) -> char {
     unsafe { std::char::from_u32_unchecked(u32::from(u)) }
 }
 
+/// How aggressively `DecomposeVietnamese` should take Vietnamese text apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecompositionLevel {
+    /// Tone marks are decomposed if there is no precomposed form for the
+    /// incoming character in windows-1258. E.g. á is not decomposed, but ý
+    /// is decomposed to y followed by combining acute and ấ is decomposed
+    /// to â followed by combining acute.
+    Windows1258,
+    /// Tone marks are always decomposed. That is, even á is decomposed.
+    /// Circumflex and breve are not detached from their base characters,
+    /// so ấ decomposes to â followed by combining acute.
+    Orthographic,
+    /// Like `Orthographic`, but circumflex, breve, and horn are also
+    /// detached from their base characters, so ấ decomposes to `a`
+    /// followed by combining circumflex followed by combining acute.
+    /// This reconstructs the individual keystrokes of a Telex/VNI-style
+    /// Vietnamese keyboard layout. Stroked d (đ/Đ) has no canonical
+    /// combining decomposition and is left atomic.
+    Full,
+}
+
+// Given a base character that may already carry a circumflex, breve, or
+// horn, returns the plain Latin base letter and the combining mark that
+// spells out the diacritic, in the order they'd be typed on a Telex/VNI
+// keyboard (i.e. before the tone mark). Characters without one of these
+// diacritics are returned unchanged with no combining mark.
+fn diacritic_to_combining(c: char) -> (char, Option<char>) {
+    match c {
+        'â' => ('a', Some('\u{0302}')),
+        'Â' => ('A', Some('\u{0302}')),
+        'ă' => ('a', Some('\u{0306}')),
+        'Ă' => ('A', Some('\u{0306}')),
+        'ê' => ('e', Some('\u{0302}')),
+        'Ê' => ('E', Some('\u{0302}')),
+        'ô' => ('o', Some('\u{0302}')),
+        'Ô' => ('O', Some('\u{0302}')),
+        'ơ' => ('o', Some('\u{031B}')),
+        'Ơ' => ('O', Some('\u{031B}')),
+        'ư' => ('u', Some('\u{031B}')),
+        'Ư' => ('U', Some('\u{031B}')),
+        _ => (c, None),
+    }
+}
+
 /// An iterator adapter yielding `char` with tone marks detached.
 #[derive(Debug)]
 pub struct DecomposeVietnamese<I> {
     delegate: I,
-    pending: char,
-    orthographic: bool,
+    pending: [char; 2],
+    pending_len: u8,
+    level: DecompositionLevel,
+}
+
+impl<I: Iterator<Item = char>> DecomposeVietnamese<I> {
+    // Queues up `tone` (if present) to be yielded after `base`, splitting
+    // `base` itself into a plain Latin letter plus a leading combining
+    // diacritic mark first when running at `DecompositionLevel::Full`.
+    // Returns the char to yield right away.
+    fn decompose(&mut self, base: char, tone: Option<char>) -> char {
+        if self.level == DecompositionLevel::Full {
+            let (ascii_base, diacritic) = diacritic_to_combining(base);
+            if let Some(diacritic) = diacritic {
+                let mut len = 0u8;
+                self.pending[len as usize] = diacritic;
+                len += 1;
+                if let Some(tone) = tone {
+                    self.pending[len as usize] = tone;
+                    len += 1;
+                }
+                self.pending_len = len;
+                return ascii_base;
+            }
+        }
+        if let Some(tone) = tone {
+            self.pending[0] = tone;
+            self.pending_len = 1;
+        }
+        base
+    }
 }
 
 impl<I: Iterator<Item = char>> Iterator for DecomposeVietnamese<I> {
@@ -275,9 +371,10 @@ impl<I: Iterator<Item = char>> Iterator for DecomposeVietnamese<I> {
 
     #[inline]
     fn next(&mut self) -> Option<char> {
-        if self.pending != '\u{0}' {
-            let c = self.pending;
-            self.pending = '\u{0}';
+        if self.pending_len > 0 {
+            let c = self.pending[0];
+            self.pending[0] = self.pending[1];
+            self.pending_len -= 1;
             return Some(c);
         }
         if let Some(c) = self.delegate.next() {
@@ -287,8 +384,7 @@ impl<I: Iterator<Item = char>> Iterator for DecomposeVietnamese<I> {
                 let val = TONE_DATA.extensions_for_vietnamese[minus_offset];
                 let base = expand(val & 0x3FF);
                 let tone = expand((val >> 10) + 0x0300);
-                self.pending = tone;
-                return Some(base);
+                return Some(self.decompose(base, Some(tone)));
             }
             if c >= '\u{C3}' && c <= '\u{0169}' {
                 let key = (s - 0xC3) as u8;
@@ -303,20 +399,18 @@ impl<I: Iterator<Item = char>> Iterator for DecomposeVietnamese<I> {
                     } else {
                         '\u{0303}'
                     };
-                    self.pending = tone;
-                    return Some(base);
+                    return Some(self.decompose(base, Some(tone)));
                 }
             }
-            if self.orthographic && c >= '\u{C0}' && c <= '\u{FA}' {
+            if self.level != DecompositionLevel::Windows1258 && c >= '\u{C0}' && c <= '\u{FA}' {
                 if let Ok(i) = TONE_DATA.windows_1258_key.binary_search(&(c as u8)) {
                     let val = TONE_DATA.windows_1258_value[i];
                     let base = char::from(val & 0x7F);
                     let tone = (val >> 7) as u16 + 0x0300;
-                    self.pending = expand(tone);
-                    return Some(base);
+                    return Some(self.decompose(base, Some(expand(tone))));
                 }
             }
-            return Some(c);
+            return Some(self.decompose(c, None));
         }
         None
     }
@@ -328,43 +422,671 @@ pub trait IterDecomposeVietnamese<I: Iterator<Item = char>> {
     /// Assuming that `self` is an iterator yielding a sequence of
     /// `char`s in Normalization Form C (this precondition is not
     /// checked!), yields a sequence of `char`s with Vietnamese tone
-    /// marks less or more decomposed. Note that the output is _not_
-    /// in Unicode Normalization Form D or any Normalization Form.
-    /// Circumflex and breve are not detached from their base characters.
-    ///
-    /// If `orthographic` is `false`, tone marks are decomposed if
-    /// there is no precomposed form form the incoming character in
-    /// windows-1258. E.g. á is not decomposed, but ý is decomposed to
-    /// y followed by combining acute and ấ is decomposed to â followed
-    /// by combining acute.
-    ///
-    /// If `orthographic` is `true`, tone marks are always decomposed.
-    /// That is, even á is decomposed.
-    fn decompose_vietnamese_tones(self, orthographic: bool) -> DecomposeVietnamese<I>;
+    /// marks (and, at `DecompositionLevel::Full`, orthographic
+    /// diacritics) decomposed according to `level`. Note that the output
+    /// is _not_ in Unicode Normalization Form D or any Normalization
+    /// Form.
+    fn decompose_vietnamese_tones(self, level: DecompositionLevel) -> DecomposeVietnamese<I>;
 }
 
 impl<I: Iterator<Item = char>> IterDecomposeVietnamese<I> for I {
     /// Assuming that `self` is an iterator yielding a sequence of
     /// `char`s in Normalization Form C (this precondition is not
     /// checked!), yields a sequence of `char`s with Vietnamese tone
-    /// marks less or more decomposed. Note that the output is _not_
-    /// in Unicode Normalization Form D or any Normalization Form.
-    /// Circumflex and breve are not detached from their base characters.
-    ///
-    /// If `orthographic` is `false`, tone marks are decomposed if
-    /// there is no precomposed form form the incoming character in
-    /// windows-1258. E.g. á is not decomposed, but ý is decomposed to
-    /// y followed by combining acute and ấ is decomposed to â followed
-    /// by combining acute.
-    ///
-    /// If `orthographic` is `true`, tone marks are always decomposed.
-    /// That is, even á is decomposed.
+    /// marks (and, at `DecompositionLevel::Full`, orthographic
+    /// diacritics) decomposed according to `level`. Note that the output
+    /// is _not_ in Unicode Normalization Form D or any Normalization
+    /// Form.
     #[inline]
-    fn decompose_vietnamese_tones(self, orthographic: bool) -> DecomposeVietnamese<I> {
+    fn decompose_vietnamese_tones(self, level: DecompositionLevel) -> DecomposeVietnamese<I> {
         DecomposeVietnamese {
             delegate: self,
-            pending: '\u{0}',
-            orthographic: orthographic,
+            pending: ['\u{0}', '\u{0}'],
+            pending_len: 0,
+            level,
+        }
+    }
+}
+
+/// Returns `true` if `c` is one of the five combining marks used to write
+/// Vietnamese tones.
+#[inline]
+fn is_vietnamese_tone_mark(c: char) -> bool {
+    match c {
+        '\u{0300}' | '\u{0301}' | '\u{0303}' | '\u{0309}' | '\u{0323}' => true,
+        _ => false,
+    }
+}
+
+// Given a base character (possibly already carrying a circumflex, breve, or
+// horn) and one of the five combining tone marks, looks up the precomposed
+// code point for the pair, if one exists, by searching the three tables in
+// `TONE_DATA` in reverse. Returns `None` if the base and tone don't combine
+// into anything representable as a single code point (e.g. consonants).
+fn compose_base_and_tone(base: char, tone: char) -> Option<char> {
+    for i in 0..TONE_DATA.windows_1258_value.len() {
+        let val = TONE_DATA.windows_1258_value[i];
+        let b = char::from(val & 0x7F);
+        let t = expand(u16::from(val >> 7) + 0x0300);
+        if b == base && t == tone {
+            return Some(char::from(TONE_DATA.windows_1258_key[i]));
+        }
+    }
+    for i in 0..TONE_DATA.middle_value.len() {
+        let val = TONE_DATA.middle_value[i];
+        let b = char::from(val & 0x7F);
+        let t = if (val & 0x5F) == b'Y' {
+            '\u{0301}'
+        } else if (val >> 7) == 0 {
+            '\u{0300}'
+        } else {
+            '\u{0303}'
+        };
+        if b == base && t == tone {
+            return Some(expand(u16::from(TONE_DATA.middle_key[i]) + 0xC3));
+        }
+    }
+    for i in 0..TONE_DATA.extensions_for_vietnamese.len() {
+        let val = TONE_DATA.extensions_for_vietnamese[i];
+        let b = expand(val & 0x3FF);
+        let t = expand((val >> 10) + 0x0300);
+        if b == base && t == tone {
+            return Some(expand(i as u16 + 0x1EA0));
+        }
+    }
+    None
+}
+
+/// An iterator adapter yielding `char` with tone marks recombined with
+/// their base characters.
+#[derive(Debug)]
+pub struct ComposeVietnameseTones<I> {
+    delegate: I,
+    pending: Option<char>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for ComposeVietnameseTones<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let base = match self.pending.take() {
+            Some(c) => c,
+            None => self.delegate.next()?,
+        };
+        match self.delegate.next() {
+            Some(c) => {
+                if is_vietnamese_tone_mark(c) {
+                    if let Some(composed) = compose_base_and_tone(base, c) {
+                        return Some(composed);
+                    }
+                }
+                self.pending = Some(c);
+                Some(base)
+            }
+            None => Some(base),
+        }
+    }
+}
+
+/// Trait that adds a `compose_vietnamese_tones` method to iterators
+/// over `char`.
+pub trait IterComposeVietnamese<I: Iterator<Item = char>> {
+    /// Assuming that `self` is an iterator yielding a sequence of base
+    /// letters (optionally already carrying a circumflex, breve, or horn)
+    /// each optionally followed by one of the five combining Vietnamese
+    /// tone marks, yields a sequence of `char`s with each base and
+    /// following tone mark recombined into its precomposed Normalization
+    /// Form C code point. This is the inverse of
+    /// `decompose_vietnamese_tones()` at `DecompositionLevel::Windows1258`
+    /// and `DecompositionLevel::Orthographic`. The combining circumflex,
+    /// breve, and horn marks emitted at `DecompositionLevel::Full` are not
+    /// recombined with their base letters.
+    ///
+    /// A base character not followed by a recognized tone mark, or
+    /// followed by a tone mark that doesn't form a known precomposed
+    /// code point together with it (e.g. a consonant followed by a tone
+    /// mark), is passed through unchanged, and the following char is
+    /// considered again as its own base.
+    fn compose_vietnamese_tones(self) -> ComposeVietnameseTones<I>;
+}
+
+impl<I: Iterator<Item = char>> IterComposeVietnamese<I> for I {
+    #[inline]
+    fn compose_vietnamese_tones(self) -> ComposeVietnameseTones<I> {
+        ComposeVietnameseTones {
+            delegate: self,
+            pending: None,
+        }
+    }
+}
+
+// Given a bare base-with-diacritic character (one of the circumflex, breve,
+// or horn letters, in either case), returns the plain ASCII base letter and
+// the VIQR modifier punctuation that spells out the diacritic. Characters
+// without one of these diacritics are returned unchanged with no modifier.
+fn diacritic_to_ascii(c: char) -> (char, Option<char>) {
+    match c {
+        'â' => ('a', Some('^')),
+        'Â' => ('A', Some('^')),
+        'ă' => ('a', Some('(')),
+        'Ă' => ('A', Some('(')),
+        'ê' => ('e', Some('^')),
+        'Ê' => ('E', Some('^')),
+        'ô' => ('o', Some('^')),
+        'Ô' => ('O', Some('^')),
+        'ơ' => ('o', Some('+')),
+        'Ơ' => ('O', Some('+')),
+        'ư' => ('u', Some('+')),
+        'Ư' => ('U', Some('+')),
+        _ => (c, None),
+    }
+}
+
+// The reverse of `diacritic_to_ascii`: given a plain ASCII vowel and a VIQR
+// modifier punctuation char, returns the precomposed diacritic letter in the
+// same case as `base`, or `None` if the combination isn't one VIQR defines.
+fn ascii_to_diacritic(base: char, modifier: char) -> Option<char> {
+    let upper = base.is_ascii_uppercase();
+    match (base.to_ascii_lowercase(), modifier) {
+        ('a', '^') => Some(if upper { 'Â' } else { 'â' }),
+        ('a', '(') => Some(if upper { 'Ă' } else { 'ă' }),
+        ('e', '^') => Some(if upper { 'Ê' } else { 'ê' }),
+        ('o', '^') => Some(if upper { 'Ô' } else { 'ô' }),
+        ('o', '+') => Some(if upper { 'Ơ' } else { 'ơ' }),
+        ('u', '+') => Some(if upper { 'Ư' } else { 'ư' }),
+        _ => None,
+    }
+}
+
+// Maps one of the five Vietnamese combining tone marks to its trailing VIQR
+// punctuation character.
+fn tone_to_viqr(tone: char) -> char {
+    match tone {
+        '\u{0300}' => '`',
+        '\u{0301}' => '\'',
+        '\u{0303}' => '~',
+        '\u{0309}' => '?',
+        '\u{0323}' => '.',
+        _ => unreachable!("not a Vietnamese tone mark"),
+    }
+}
+
+// The reverse of `tone_to_viqr`. Returns `None` for punctuation that isn't
+// one of the five VIQR tone markers.
+fn viqr_to_tone(c: char) -> Option<char> {
+    match c {
+        '`' => Some('\u{0300}'),
+        '\'' => Some('\u{0301}'),
+        '~' => Some('\u{0303}'),
+        '?' => Some('\u{0309}'),
+        '.' => Some('\u{0323}'),
+        _ => None,
+    }
+}
+
+#[inline]
+fn is_vietnamese_vowel(c: char) -> bool {
+    match c {
+        'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => true,
+        _ => false,
+    }
+}
+
+// Decomposes a single NFC `char` into up to three VIQR output chars: the
+// ASCII base letter, an optional diacritic modifier, and an optional
+// trailing tone mark. Reuses the same `TONE_DATA` tables as
+// `DecomposeVietnamese`, since VIQR's base+modifier+tone shape is the ASCII
+// spelling of exactly the same decomposition.
+fn encode_viqr_char(c: char) -> (char, Option<char>, Option<char>) {
+    if c == 'đ' {
+        return ('d', Some('d'), None);
+    }
+    if c == 'Đ' {
+        return ('D', Some('D'), None);
+    }
+    let s = c as usize;
+    let minus_offset = s.wrapping_sub(0x1EA0);
+    if minus_offset < TONE_DATA.extensions_for_vietnamese.len() {
+        let val = TONE_DATA.extensions_for_vietnamese[minus_offset];
+        let base = expand(val & 0x3FF);
+        let tone = expand((val >> 10) + 0x0300);
+        let (ascii_base, modifier) = diacritic_to_ascii(base);
+        return (ascii_base, modifier, Some(tone_to_viqr(tone)));
+    }
+    if c >= '\u{C3}' && c <= '\u{0169}' {
+        let key = (s - 0xC3) as u8;
+        if let Ok(i) = TONE_DATA.middle_key.binary_search(&key) {
+            let val = TONE_DATA.middle_value[i];
+            let base = char::from(val & 0x7F);
+            let tone = if (val & 0x5F) == b'Y' {
+                '\u{0301}'
+            } else if (val >> 7) == 0 {
+                '\u{0300}'
+            } else {
+                '\u{0303}'
+            };
+            return (base, None, Some(tone_to_viqr(tone)));
+        }
+    }
+    if c >= '\u{C0}' && c <= '\u{FA}' {
+        if let Ok(i) = TONE_DATA.windows_1258_key.binary_search(&(c as u8)) {
+            let val = TONE_DATA.windows_1258_value[i];
+            let base = char::from(val & 0x7F);
+            let tone = (val >> 7) as u16 + 0x0300;
+            return (base, None, Some(tone_to_viqr(expand(tone))));
+        }
+    }
+    let (ascii_base, modifier) = diacritic_to_ascii(c);
+    (ascii_base, modifier, None)
+}
+
+/// An iterator adapter yielding `char`s that spell Vietnamese text out in
+/// VIQR, the 7-bit ASCII mnemonic convention for Vietnamese.
+#[derive(Debug)]
+pub struct EncodeViqr<I> {
+    delegate: I,
+    pending: [char; 2],
+    pending_len: u8,
+}
+
+impl<I: Iterator<Item = char>> Iterator for EncodeViqr<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        if self.pending_len > 0 {
+            let c = self.pending[0];
+            self.pending[0] = self.pending[1];
+            self.pending_len -= 1;
+            return Some(c);
+        }
+        let c = self.delegate.next()?;
+        let (first, second, third) = encode_viqr_char(c);
+        let mut len = 0u8;
+        if let Some(modifier) = second {
+            self.pending[len as usize] = modifier;
+            len += 1;
+        }
+        if let Some(tone) = third {
+            self.pending[len as usize] = tone;
+            len += 1;
+        }
+        self.pending_len = len;
+        Some(first)
+    }
+}
+
+/// Trait that adds an `encode_viqr` method to iterators over `char`.
+pub trait IterEncodeViqr<I: Iterator<Item = char>> {
+    /// Assuming that `self` is an iterator yielding a sequence of `char`s
+    /// in Normalization Form C (this precondition is not checked!), yields
+    /// a sequence of plain ASCII `char`s with each Vietnamese letter spelled
+    /// out as its base letter followed by its diacritic modifier(s) (`^`
+    /// for circumflex, `(` for breve, `+` for horn, and `dd`/`DD` for the
+    /// stroked D) and then its tone mark (`` ` `` grave, `'` acute, `?`
+    /// hook above, `~` tilde, `.` dot below). Non-Vietnamese `char`s are
+    /// passed through unchanged.
+    fn encode_viqr(self) -> EncodeViqr<I>;
+}
+
+impl<I: Iterator<Item = char>> IterEncodeViqr<I> for I {
+    #[inline]
+    fn encode_viqr(self) -> EncodeViqr<I> {
+        EncodeViqr {
+            delegate: self,
+            pending: ['\u{0}', '\u{0}'],
+            pending_len: 0,
+        }
+    }
+}
+
+/// An iterator adapter yielding `char`s with VIQR-encoded Vietnamese text
+/// recomposed into precomposed Normalization Form C `char`s.
+#[derive(Debug)]
+pub struct DecodeViqr<I> {
+    delegate: I,
+    pending: Option<char>,
+}
+
+impl<I: Iterator<Item = char>> DecodeViqr<I> {
+    #[inline]
+    fn pull(&mut self) -> Option<char> {
+        self.pending.take().or_else(|| self.delegate.next())
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for DecodeViqr<I> {
+    type Item = char;
+
+    #[inline]
+    fn next(&mut self) -> Option<char> {
+        let base = self.pull()?;
+        if base == 'd' || base == 'D' {
+            if let Some(c2) = self.pull() {
+                if c2 == base {
+                    return Some(if base == 'D' { 'Đ' } else { 'đ' });
+                }
+                self.pending = Some(c2);
+            }
+            return Some(base);
+        }
+        if is_vietnamese_vowel(base) {
+            if let Some(c2) = self.pull() {
+                if let Some(diacritic) = ascii_to_diacritic(base, c2) {
+                    if let Some(c3) = self.pull() {
+                        if let Some(tone) = viqr_to_tone(c3) {
+                            if let Some(composed) = compose_base_and_tone(diacritic, tone) {
+                                return Some(composed);
+                            }
+                        }
+                        self.pending = Some(c3);
+                    }
+                    return Some(diacritic);
+                }
+                if let Some(tone) = viqr_to_tone(c2) {
+                    if let Some(composed) = compose_base_and_tone(base, tone) {
+                        return Some(composed);
+                    }
+                }
+                self.pending = Some(c2);
+            }
+            return Some(base);
+        }
+        Some(base)
+    }
+}
+
+/// Trait that adds a `decode_viqr` method to iterators over `char`.
+pub trait IterDecodeViqr<I: Iterator<Item = char>> {
+    /// Assuming that `self` is an iterator yielding plain ASCII VIQR-encoded
+    /// Vietnamese text, yields a sequence of `char`s in Normalization Form C
+    /// with each recognized `base [modifier] [tone]` sequence recomposed
+    /// into its precomposed code point. Non-Vietnamese ASCII is passed
+    /// through unchanged.
+    fn decode_viqr(self) -> DecodeViqr<I>;
+}
+
+impl<I: Iterator<Item = char>> IterDecodeViqr<I> for I {
+    #[inline]
+    fn decode_viqr(self) -> DecodeViqr<I> {
+        DecodeViqr {
+            delegate: self,
+            pending: None,
+        }
+    }
+}
+
+#[repr(align(64))] // Align to cache lines
+struct Windows1258Reverse {
+    key: [u16; 128],
+    value: [u8; 128],
+}
+
+// The windows-1258 code page assigns bytes 0x80-0xFF to the code points
+// below (bytes 0x00-0x7F are plain ASCII). This table is the same mapping
+// sorted by code point so that `encode_windows_1258_char` can find the byte
+// for a given `char` with a binary search.
+static WINDOWS_1258_REVERSE: Windows1258Reverse = Windows1258Reverse {
+    key: [
+        0x0081, 0x008A, 0x008D, 0x008E, 0x008F, 0x0090, 0x009A, 0x009D, 0x009E, 0x00A0, 0x00A1,
+        0x00A2, 0x00A3, 0x00A4, 0x00A5, 0x00A6, 0x00A7, 0x00A8, 0x00A9, 0x00AA, 0x00AB, 0x00AC,
+        0x00AD, 0x00AE, 0x00AF, 0x00B0, 0x00B1, 0x00B2, 0x00B3, 0x00B4, 0x00B5, 0x00B6, 0x00B7,
+        0x00B8, 0x00B9, 0x00BA, 0x00BB, 0x00BC, 0x00BD, 0x00BE, 0x00BF, 0x00C0, 0x00C1, 0x00C2,
+        0x00C4, 0x00C5, 0x00C6, 0x00C7, 0x00C8, 0x00C9, 0x00CA, 0x00CB, 0x00CD, 0x00CE, 0x00CF,
+        0x00D1, 0x00D3, 0x00D4, 0x00D6, 0x00D7, 0x00D8, 0x00D9, 0x00DA, 0x00DB, 0x00DC, 0x00DF,
+        0x00E0, 0x00E1, 0x00E2, 0x00E4, 0x00E5, 0x00E6, 0x00E7, 0x00E8, 0x00E9, 0x00EA, 0x00EB,
+        0x00ED, 0x00EE, 0x00EF, 0x00F1, 0x00F3, 0x00F4, 0x00F6, 0x00F7, 0x00F8, 0x00F9, 0x00FA,
+        0x00FB, 0x00FC, 0x00FF, 0x0102, // Ă
+        0x0103, // ă
+        0x0110, // Đ
+        0x0111, // đ
+        0x0152, 0x0153, 0x0178, 0x0192, 0x01A0, // Ơ
+        0x01A1, // ơ
+        0x01AF, // Ư
+        0x01B0, // ư
+        0x02C6, 0x02DC, 0x0300, // Combining grave accent
+        0x0301, // Combining acute accent
+        0x0303, // Combining tilde
+        0x0309, // Combining hook above
+        0x0323, // Combining dot below
+        0x2013, 0x2014, 0x2018, 0x2019, 0x201A, 0x201C, 0x201D, 0x201E, 0x2020, 0x2021, 0x2022,
+        0x2026, 0x2030, 0x2039, 0x203A, 0x20AB, 0x20AC, 0x2122,
+    ],
+    value: [
+        0x81, 0x8A, 0x8D, 0x8E, 0x8F, 0x90, 0x9A, 0x9D, 0x9E, 0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5,
+        0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xAB, 0xAC, 0xAD, 0xAE, 0xAF, 0xB0, 0xB1, 0xB2, 0xB3, 0xB4,
+        0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xBB, 0xBC, 0xBD, 0xBE, 0xBF, 0xC0, 0xC1, 0xC2, 0xC4,
+        0xC5, 0xC6, 0xC7, 0xC8, 0xC9, 0xCA, 0xCB, 0xCD, 0xCE, 0xCF, 0xD1, 0xD3, 0xD4, 0xD6, 0xD7,
+        0xD8, 0xD9, 0xDA, 0xDB, 0xDC, 0xDF, 0xE0, 0xE1, 0xE2, 0xE4, 0xE5, 0xE6, 0xE7, 0xE8, 0xE9,
+        0xEA, 0xEB, 0xED, 0xEE, 0xEF, 0xF1, 0xF3, 0xF4, 0xF6, 0xF7, 0xF8, 0xF9, 0xFA, 0xFB, 0xFC,
+        0xFF, 0xC3, 0xE3, 0xD0, 0xF0, 0x8C, 0x9C, 0x9F, 0x83, 0xD5, 0xF5, 0xDD, 0xFD, 0x88, 0x98,
+        0xCC, 0xEC, 0xDE, 0xD2, 0xF2, 0x96, 0x97, 0x91, 0x92, 0x82, 0x93, 0x94, 0x84, 0x86, 0x87,
+        0x95, 0x85, 0x89, 0x8B, 0x9B, 0xFE, 0x80, 0x99,
+    ],
+};
+
+// Returns the windows-1258 byte for `c`, or `None` if `c` has no
+// representation in windows-1258.
+fn encode_windows_1258_char(c: char) -> Option<u8> {
+    let u = c as u32;
+    if u < 0x80 {
+        return Some(u as u8);
+    }
+    if u > 0xFFFF {
+        return None;
+    }
+    let key = u as u16;
+    WINDOWS_1258_REVERSE
+        .key
+        .binary_search(&key)
+        .ok()
+        .map(|i| WINDOWS_1258_REVERSE.value[i])
+}
+
+/// A `char` with no representation in windows-1258, returned by
+/// `EncodeWindows1258` when it encounters one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnrepresentableError(pub char);
+
+impl fmt::Display for UnrepresentableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "U+{:04X} has no representation in windows-1258",
+            self.0 as u32
+        )
+    }
+}
+
+impl std::error::Error for UnrepresentableError {}
+
+/// An iterator adapter yielding windows-1258 bytes, decomposing Vietnamese
+/// tone marks that don't otherwise fit into windows-1258 along the way.
+#[derive(Debug)]
+pub struct EncodeWindows1258<I> {
+    delegate: DecomposeVietnamese<I>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for EncodeWindows1258<I> {
+    type Item = Result<u8, UnrepresentableError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<u8, UnrepresentableError>> {
+        let c = self.delegate.next()?;
+        match encode_windows_1258_char(c) {
+            Some(b) => Some(Ok(b)),
+            None => Some(Err(UnrepresentableError(c))),
+        }
+    }
+}
+
+/// Trait that adds an `encode_windows_1258` method to iterators over
+/// `char`.
+pub trait IterEncodeWindows1258<I: Iterator<Item = char>> {
+    /// Assuming that `self` is an iterator yielding a sequence of `char`s
+    /// in Normalization Form C (this precondition is not checked!), yields
+    /// a sequence of windows-1258 bytes, internally decomposing tone marks
+    /// that have no precomposed windows-1258 representation exactly like
+    /// `decompose_vietnamese_tones(DecompositionLevel::Windows1258)` would.
+    /// `char`s with no windows-1258 representation at all yield
+    /// `Err(UnrepresentableError)` instead of a byte.
+    fn encode_windows_1258(self) -> EncodeWindows1258<I>;
+}
+
+impl<I: Iterator<Item = char>> IterEncodeWindows1258<I> for I {
+    #[inline]
+    fn encode_windows_1258(self) -> EncodeWindows1258<I> {
+        EncodeWindows1258 {
+            delegate: self.decompose_vietnamese_tones(DecompositionLevel::Windows1258),
+        }
+    }
+}
+
+// The reverse of `diacritic_to_combining`: given a plain Latin base letter
+// and a combining circumflex, breve, or horn, returns the precomposed
+// orthographic unit, in the same case as `base`.
+fn compose_starter_and_diacritic(base: char, diacritic: char) -> Option<char> {
+    match (base, diacritic) {
+        ('a', '\u{0302}') => Some('â'),
+        ('A', '\u{0302}') => Some('Â'),
+        ('a', '\u{0306}') => Some('ă'),
+        ('A', '\u{0306}') => Some('Ă'),
+        ('e', '\u{0302}') => Some('ê'),
+        ('E', '\u{0302}') => Some('Ê'),
+        ('o', '\u{0302}') => Some('ô'),
+        ('O', '\u{0302}') => Some('Ô'),
+        ('o', '\u{031B}') => Some('ơ'),
+        ('O', '\u{031B}') => Some('Ơ'),
+        ('u', '\u{031B}') => Some('ư'),
+        ('U', '\u{031B}') => Some('Ư'),
+        _ => None,
+    }
+}
+
+#[inline]
+fn is_orthographic_diacritic_mark(c: char) -> bool {
+    matches!(c, '\u{0302}' | '\u{0306}' | '\u{031B}')
+}
+
+/// An iterator adapter that canonically reorders and composes Vietnamese
+/// base+diacritic+tone sequences, so that `DecomposeVietnamese` (which
+/// assumes NFC input) can be fed arbitrary decomposed or
+/// non-canonically-ordered Vietnamese text robustly.
+#[derive(Debug)]
+pub struct NormalizeVietnameseTones<I> {
+    delegate: I,
+    lookahead: Option<char>,
+    pending: [char; 2],
+    pending_len: u8,
+}
+
+impl<I: Iterator<Item = char>> NormalizeVietnameseTones<I> {
+    #[inline]
+    fn pull(&mut self) -> Option<char> {
+        self.lookahead.take().or_else(|| self.delegate.next())
+    }
+}
+
+impl<I: Iterator<Item = char>> Iterator for NormalizeVietnameseTones<I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        if self.pending_len > 0 {
+            let c = self.pending[0];
+            self.pending[0] = self.pending[1];
+            self.pending_len -= 1;
+            return Some(c);
+        }
+        let base = self.pull()?;
+        let m1 = match self.pull() {
+            Some(m1) => m1,
+            None => return Some(base),
+        };
+        if is_orthographic_diacritic_mark(m1) {
+            // Canonical order: diacritic (circumflex/breve/horn, an
+            // orthographic unit) before the tone mark.
+            let tone = match self.pull() {
+                Some(m2) if is_vietnamese_tone_mark(m2) => Some(m2),
+                Some(m2) => {
+                    self.lookahead = Some(m2);
+                    None
+                }
+                None => None,
+            };
+            if let Some(with_diacritic) = compose_starter_and_diacritic(base, m1) {
+                if let Some(tone) = tone {
+                    if let Some(composed) = compose_base_and_tone(with_diacritic, tone) {
+                        return Some(composed);
+                    }
+                    self.pending[0] = tone;
+                    self.pending_len = 1;
+                }
+                return Some(with_diacritic);
+            }
+            // `m1` doesn't combine with `base` after all; don't invent a
+            // form, just put the marks back in their original order.
+            self.pending[0] = m1;
+            self.pending_len = 1;
+            if let Some(tone) = tone {
+                self.pending[1] = tone;
+                self.pending_len = 2;
+            }
+            return Some(base);
+        }
+        if is_vietnamese_tone_mark(m1) {
+            // The tone mark arrived before a following diacritic mark,
+            // which is out of canonical order; reorder them.
+            match self.pull() {
+                Some(m2) if is_orthographic_diacritic_mark(m2) => {
+                    if let Some(with_diacritic) = compose_starter_and_diacritic(base, m2) {
+                        if let Some(composed) = compose_base_and_tone(with_diacritic, m1) {
+                            return Some(composed);
+                        }
+                        self.pending[0] = m1;
+                        self.pending_len = 1;
+                        return Some(with_diacritic);
+                    }
+                    // `m2` doesn't combine with `base`; fall back to
+                    // just composing the tone, and hold `m2` for later.
+                    self.lookahead = Some(m2);
+                }
+                Some(m2) => self.lookahead = Some(m2),
+                None => {}
+            }
+            if let Some(composed) = compose_base_and_tone(base, m1) {
+                return Some(composed);
+            }
+            self.pending[0] = m1;
+            self.pending_len = 1;
+            return Some(base);
+        }
+        self.lookahead = Some(m1);
+        Some(base)
+    }
+}
+
+/// Trait that adds a `normalize_vietnamese_tones` method to iterators over
+/// `char`.
+pub trait IterNormalizeVietnameseTones<I: Iterator<Item = char>> {
+    /// Makes `self` robust to Vietnamese text that isn't in Normalization
+    /// Form C: composes base+diacritic+tone sequences, also when a tone
+    /// mark comes before the diacritic mark (circumflex, breve, or horn)
+    /// instead of after it, before handing
+    /// the resulting `char`s onward (e.g. to
+    /// `decompose_vietnamese_tones()`). This is opt-in: callers who
+    /// already have NFC input (e.g. via `unic_normal`) don't need it.
+    fn normalize_vietnamese_tones(self) -> NormalizeVietnameseTones<I>;
+}
+
+impl<I: Iterator<Item = char>> IterNormalizeVietnameseTones<I> for I {
+    #[inline]
+    fn normalize_vietnamese_tones(self) -> NormalizeVietnameseTones<I> {
+        NormalizeVietnameseTones {
+            delegate: self,
+            lookahead: None,
+            pending: ['\u{0}', '\u{0}'],
+            pending_len: 0,
         }
     }
 }
@@ -375,12 +1097,20 @@ mod tests {
     use unic_normal::StrNormalForm;
 
     fn check(nfc: char, base: char, tone: char) {
-        let mut decompose_vietnamese = std::iter::once(nfc).decompose_vietnamese_tones(true);
+        let mut decompose_vietnamese = std::iter::once(nfc)
+            .decompose_vietnamese_tones(DecompositionLevel::Orthographic);
         assert_eq!(decompose_vietnamese.next(), Some(base));
         assert_eq!(decompose_vietnamese.next(), Some(tone));
         assert_eq!(decompose_vietnamese.next(), None);
     }
 
+    fn check_compose(base: char, tone: char, nfc: char) {
+        let mut compose_vietnamese =
+            vec![base, tone].into_iter().compose_vietnamese_tones();
+        assert_eq!(compose_vietnamese.next(), Some(nfc));
+        assert_eq!(compose_vietnamese.next(), None);
+    }
+
     #[test]
     fn test_tones() {
         let bases = [
@@ -398,4 +1128,245 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_windows_1258_level_leaves_a_grave_precomposed() {
+        // à has a windows-1258 representation, so DecompositionLevel::Windows1258
+        // should leave it alone, unlike DecompositionLevel::Orthographic.
+        let mut decompose_vietnamese = std::iter::once('à')
+            .decompose_vietnamese_tones(DecompositionLevel::Windows1258);
+        assert_eq!(decompose_vietnamese.next(), Some('à'));
+        assert_eq!(decompose_vietnamese.next(), None);
+    }
+
+    #[test]
+    fn test_full_level_detaches_circumflex_breve_and_horn() {
+        // ộ -> o, combining circumflex, combining dot below
+        let mut decompose_vietnamese =
+            std::iter::once('ộ').decompose_vietnamese_tones(DecompositionLevel::Full);
+        assert_eq!(decompose_vietnamese.next(), Some('o'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{0302}'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{0323}'));
+        assert_eq!(decompose_vietnamese.next(), None);
+
+        // ắ -> a, combining breve, combining acute
+        let mut decompose_vietnamese =
+            std::iter::once('ắ').decompose_vietnamese_tones(DecompositionLevel::Full);
+        assert_eq!(decompose_vietnamese.next(), Some('a'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{0306}'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{0301}'));
+        assert_eq!(decompose_vietnamese.next(), None);
+
+        // ử -> u, combining horn, combining hook above
+        let mut decompose_vietnamese =
+            std::iter::once('ử').decompose_vietnamese_tones(DecompositionLevel::Full);
+        assert_eq!(decompose_vietnamese.next(), Some('u'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{031B}'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{0309}'));
+        assert_eq!(decompose_vietnamese.next(), None);
+
+        // â alone (no tone) -> a, combining circumflex
+        let mut decompose_vietnamese =
+            std::iter::once('â').decompose_vietnamese_tones(DecompositionLevel::Full);
+        assert_eq!(decompose_vietnamese.next(), Some('a'));
+        assert_eq!(decompose_vietnamese.next(), Some('\u{0302}'));
+        assert_eq!(decompose_vietnamese.next(), None);
+    }
+
+    #[test]
+    fn test_full_level_keeps_stroked_d_atomic() {
+        let mut decompose_vietnamese =
+            std::iter::once('đ').decompose_vietnamese_tones(DecompositionLevel::Full);
+        assert_eq!(decompose_vietnamese.next(), Some('đ'));
+        assert_eq!(decompose_vietnamese.next(), None);
+    }
+
+    #[test]
+    fn test_compose_tones() {
+        let bases = [
+            'A', 'a', 'Ă', 'ă', 'Â', 'â', 'E', 'e', 'Ê', 'ê', 'I', 'i', 'O', 'o', 'Ô', 'ô',
+            'Ơ', 'ơ', 'U', 'u', 'Ư', 'ư', 'Y', 'y',
+        ];
+        let tones = ['\u{0300}', '\u{0309}', '\u{0303}', '\u{0301}', '\u{0323}'];
+        for &base in bases.iter() {
+            for &tone in tones.iter() {
+                let mut paired = String::new();
+                paired.push(base);
+                paired.push(tone);
+                let nfc = paired.nfc().next().unwrap();
+                check_compose(base, tone, nfc);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compose_passes_through_unmatched_tone() {
+        // 'B' never combines with a tone mark, so both chars come through
+        // unchanged rather than being silently dropped or invented.
+        let mut compose_vietnamese = vec!['B', '\u{0301}'].into_iter().compose_vietnamese_tones();
+        assert_eq!(compose_vietnamese.next(), Some('B'));
+        assert_eq!(compose_vietnamese.next(), Some('\u{0301}'));
+        assert_eq!(compose_vietnamese.next(), None);
+    }
+
+    fn check_viqr(nfc: char, viqr: &str) {
+        let encoded: String = std::iter::once(nfc).encode_viqr().collect();
+        assert_eq!(encoded, viqr);
+        let decoded: String = viqr.chars().decode_viqr().collect();
+        assert_eq!(decoded, nfc.to_string());
+    }
+
+    #[test]
+    fn test_viqr_tones() {
+        let bases = [
+            'A', 'a', 'Ă', 'ă', 'Â', 'â', 'E', 'e', 'Ê', 'ê', 'I', 'i', 'O', 'o', 'Ô', 'ô',
+            'Ơ', 'ơ', 'U', 'u', 'Ư', 'ư', 'Y', 'y',
+        ];
+        let tones = ['\u{0300}', '\u{0309}', '\u{0303}', '\u{0301}', '\u{0323}'];
+        let viqr_tones = ['`', '?', '~', '\'', '.'];
+        for &base in bases.iter() {
+            let (ascii_base, modifier) = diacritic_to_ascii(base);
+            for (&tone, &viqr_tone) in tones.iter().zip(viqr_tones.iter()) {
+                let mut paired = String::new();
+                paired.push(base);
+                paired.push(tone);
+                let nfc = paired.nfc().next().unwrap();
+                let mut viqr = String::new();
+                viqr.push(ascii_base);
+                if let Some(modifier) = modifier {
+                    viqr.push(modifier);
+                }
+                viqr.push(viqr_tone);
+                check_viqr(nfc, &viqr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_viqr_bare_diacritics() {
+        check_viqr('â', "a^");
+        check_viqr('ă', "a(");
+        check_viqr('ơ', "o+");
+        check_viqr('ư', "u+");
+    }
+
+    #[test]
+    fn test_viqr_stroked_d() {
+        check_viqr('đ', "dd");
+        check_viqr('Đ', "DD");
+    }
+
+    #[test]
+    fn test_viqr_passthrough() {
+        let encoded: String = "Hello, world!".chars().encode_viqr().collect();
+        assert_eq!(encoded, "Hello, world!");
+        let decoded: String = "Hello, world!".chars().decode_viqr().collect();
+        assert_eq!(decoded, "Hello, world!");
+    }
+
+    #[test]
+    fn test_encode_windows_1258_ascii() {
+        let bytes: Vec<u8> = "Hello, world!"
+            .chars()
+            .encode_windows_1258()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(bytes, b"Hello, world!");
+    }
+
+    #[test]
+    fn test_encode_windows_1258_tones() {
+        // á has a windows-1258 representation of its own, so it isn't
+        // decomposed at all.
+        let bytes: Vec<u8> = std::iter::once('á')
+            .encode_windows_1258()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(bytes, vec![0xE1]);
+
+        // ý has no precomposed windows-1258 representation, so it's
+        // decomposed to y followed by combining acute.
+        let bytes: Vec<u8> = std::iter::once('ý')
+            .encode_windows_1258()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(bytes, vec![0x79, 0xEC]);
+
+        // ấ doesn't, so it's decomposed to â followed by combining acute,
+        // and the combining acute has its own windows-1258 spacing-tone byte.
+        let bytes: Vec<u8> = std::iter::once('ấ')
+            .encode_windows_1258()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(bytes, vec![0xE2, 0xEC]);
+
+        // đ has a direct windows-1258 byte of its own.
+        let bytes: Vec<u8> = std::iter::once('đ')
+            .encode_windows_1258()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(bytes, vec![0xF0]);
+    }
+
+    #[test]
+    fn test_encode_windows_1258_unrepresentable() {
+        let mut encoded = std::iter::once('λ').encode_windows_1258();
+        assert_eq!(encoded.next(), Some(Err(UnrepresentableError('λ'))));
+        assert_eq!(encoded.next(), None);
+    }
+
+    #[test]
+    fn test_normalize_composes_base_diacritic_tone() {
+        // a + combining circumflex + combining acute -> ấ
+        let composed: String = vec!['a', '\u{0302}', '\u{0301}']
+            .into_iter()
+            .normalize_vietnamese_tones()
+            .collect();
+        assert_eq!(composed, "ấ");
+    }
+
+    #[test]
+    fn test_normalize_reorders_tone_before_diacritic() {
+        // a + combining acute + combining circumflex (wrong order) -> ấ
+        let composed: String = vec!['a', '\u{0301}', '\u{0302}']
+            .into_iter()
+            .normalize_vietnamese_tones()
+            .collect();
+        assert_eq!(composed, "ấ");
+    }
+
+    #[test]
+    fn test_normalize_composes_bare_diacritic() {
+        let composed: String = vec!['a', '\u{0302}']
+            .into_iter()
+            .normalize_vietnamese_tones()
+            .collect();
+        assert_eq!(composed, "â");
+    }
+
+    #[test]
+    fn test_normalize_passes_through_non_vietnamese() {
+        let composed: String = "Hello, world!"
+            .chars()
+            .normalize_vietnamese_tones()
+            .collect();
+        assert_eq!(composed, "Hello, world!");
+    }
+
+    #[test]
+    fn test_normalize_then_decompose_windows_1258_round_trip() {
+        // Raw Telex-style keystrokes for "ấ" fed straight into the
+        // Windows1258-level decomposer by way of the normalizer should come
+        // back out as "â" + combining acute, exactly as if "ấ" itself had
+        // been the (NFC) input.
+        let decomposed: String = vec!['a', '\u{0302}', '\u{0301}']
+            .into_iter()
+            .normalize_vietnamese_tones()
+            .decompose_vietnamese_tones(DecompositionLevel::Windows1258)
+            .collect();
+        let mut expected = String::new();
+        expected.push('â');
+        expected.push('\u{0301}');
+        assert_eq!(decomposed, expected);
+    }
 }